@@ -1,49 +1,301 @@
 // Copyright (c) Microsoft. All rights reserved.
 
-use std::marker::PhantomData;
+use std::cmp;
+use std::time::{Duration, Instant};
 
-use error::Error;
-use module::{Module, ModuleRuntime, ModuleSpec};
+use failure::Fail;
+use futures::future::{self, Either, Loop};
+use futures::Future;
+use tokio::timer::Delay;
 
-use futures::{future, Future};
+use error::{Error, ErrorKind};
+use identity::IdentityManager;
+use module::{Module, ModuleRuntime, ModuleSpec, ModuleStatus};
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const HEALTHY_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
 
 pub struct Watchdog<M, I> {
-    runtime: PhantomData<M>,
-    identity: PhantomData<I>,
+    runtime: M,
+    identity: I,
 }
 
 impl<M, I> Watchdog<M, I>
 where
     M: 'static + ModuleRuntime + Clone,
+    I: 'static + IdentityManager + Clone,
 {
-    pub fn new(_: M, _: I) -> Self {
-        Watchdog {
-            runtime: PhantomData,
-            identity: PhantomData,
-        }
+    pub fn new(runtime: M, identity: I) -> Self {
+        Watchdog { runtime, identity }
     }
 
     pub fn run_until<F>(
         self,
-        _spec: ModuleSpec<<M::Module as Module>::Config>,
-        _module_id: &str,
-        _shutdown_signal: F,
+        spec: ModuleSpec<<M::Module as Module>::Config>,
+        module_id: &str,
+        shutdown_signal: F,
     ) -> impl Future<Item = (), Error = Error>
     where
         F: Future<Item = (), Error = ()> + 'static,
     {
-        future::ok(())
+        let module_id = module_id.to_owned();
+        let monitor = monitor_loop(self.runtime, self.identity, spec, module_id);
+
+        // select() resolves as soon as either future does, so a shutdown
+        // signal cuts the current backoff delay short instead of running it out.
+        shutdown_signal
+            .then(|_| Ok(()))
+            .select(monitor)
+            .then(|result| match result {
+                Ok(((), _)) | Err(((), _)) => future::ok(()),
+            })
     }
 }
 
 pub fn start_watchdog<M, I>(
-    _runtime: M,
-    _id_mgr: I,
-    _spec: ModuleSpec<<M::Module as Module>::Config>,
-    _module_id: String,
+    runtime: M,
+    id_mgr: I,
+    spec: ModuleSpec<<M::Module as Module>::Config>,
+    module_id: String,
 ) -> impl Future<Item = (), Error = Error>
 where
-    M: 'static + ModuleRuntime,
+    M: 'static + ModuleRuntime + Clone,
+    I: 'static + IdentityManager + Clone,
+{
+    Watchdog::new(runtime, id_mgr).run_until(spec, &module_id, future::empty())
+}
+
+type LoopState<M, I> = (
+    M,
+    I,
+    ModuleSpec<<<M as ModuleRuntime>::Module as Module>::Config>,
+    String,
+    Duration,
+    Option<Instant>,
+);
+
+/// Polls the module's status on `POLL_INTERVAL`, restarting it (provisioning
+/// its identity and creating it first if it does not exist yet) with
+/// exponential backoff whenever it is found stopped. Never resolves with an
+/// error; failures to query or restart the module are logged and retried on
+/// the next tick.
+fn monitor_loop<M, I>(
+    runtime: M,
+    identity: I,
+    spec: ModuleSpec<<M::Module as Module>::Config>,
+    module_id: String,
+) -> impl Future<Item = (), Error = ()>
+where
+    M: 'static + ModuleRuntime + Clone,
+    I: 'static + IdentityManager + Clone,
 {
-    future::ok(())
+    future::loop_fn(
+        (runtime, identity, spec, module_id, INITIAL_BACKOFF, None),
+        |(runtime, identity, spec, module_id, backoff, healthy_since): LoopState<M, I>| {
+            query_status(runtime.clone(), module_id.clone()).then(move |status| {
+                let status = status.unwrap_or(None);
+
+                let restart: Box<dyn Future<Item = (), Error = Error> + Send> = match status {
+                    Some(false) => Box::new(restart_module(
+                        runtime.clone(),
+                        identity.clone(),
+                        spec.clone(),
+                        module_id.clone(),
+                    )),
+                    Some(true) | None => Box::new(future::ok(())),
+                };
+
+                restart.then(move |restart_result| {
+                    if let Err(err) = restart_result {
+                        warn!("could not restart module {}: {}", module_id, err);
+                    }
+
+                    let now = Instant::now();
+                    let (next_backoff, next_healthy_since, delay) =
+                        advance(status, backoff, healthy_since, now);
+
+                    Delay::new(now + delay).then(move |_| {
+                        future::ok(Loop::Continue((
+                            runtime,
+                            identity,
+                            spec,
+                            module_id,
+                            next_backoff,
+                            next_healthy_since,
+                        )))
+                    })
+                })
+            })
+        },
+    )
+}
+
+/// Computes the backoff, healthy-since timestamp and next poll delay given
+/// the module's status on this tick. `status` is `Some(true)` when running,
+/// `Some(false)` when stopped, and `None` when its status could not be
+/// determined this tick (in which case the prior state is left untouched so
+/// a transient query failure doesn't masquerade as either a restart or a
+/// healthy tick).
+fn advance(
+    status: Option<bool>,
+    backoff: Duration,
+    healthy_since: Option<Instant>,
+    now: Instant,
+) -> (Duration, Option<Instant>, Duration) {
+    match status {
+        Some(true) => {
+            let healthy_since = healthy_since.unwrap_or(now);
+            let backoff = if now.duration_since(healthy_since) >= HEALTHY_COOLDOWN {
+                INITIAL_BACKOFF
+            } else {
+                backoff
+            };
+            (backoff, Some(healthy_since), POLL_INTERVAL)
+        }
+        Some(false) => {
+            let backoff = cmp::min(backoff * 2, MAX_BACKOFF);
+            (backoff, None, backoff)
+        }
+        None => (backoff, healthy_since, POLL_INTERVAL),
+    }
+}
+
+/// Returns `Some(true)` if the module is running, `Some(false)` if it is
+/// known to be stopped (or absent), or `None` if the runtime could not be
+/// queried this tick. Never resolves with an error: a query failure is
+/// logged and folded into `None` so callers can treat it as "unknown"
+/// rather than "down".
+fn query_status<M>(runtime: M, module_id: String) -> impl Future<Item = Option<bool>, Error = Error>
+where
+    M: ModuleRuntime,
+{
+    let module_id_for_log = module_id.clone();
+
+    runtime
+        .list()
+        .map_err(|err| Error::from(err.context(ErrorKind::Watchdog)))
+        .and_then(move |modules| {
+            modules
+                .into_iter()
+                .find(|module| module.name() == module_id)
+                .map_or_else(
+                    || Either::A(future::ok(Some(false))),
+                    |module| {
+                        Either::B(
+                            module
+                                .runtime_state()
+                                .map_err(|err| Error::from(err.context(ErrorKind::Watchdog)))
+                                .map(|state| Some(*state.status() == ModuleStatus::Running)),
+                        )
+                    },
+                )
+        }).or_else(move |err| {
+            warn!(
+                "could not query status of module {}: {}",
+                module_id_for_log, err
+            );
+            future::ok(None)
+        })
+}
+
+fn restart_module<M, I>(
+    runtime: M,
+    identity: I,
+    spec: ModuleSpec<<M::Module as Module>::Config>,
+    module_id: String,
+) -> impl Future<Item = (), Error = Error>
+where
+    M: 'static + ModuleRuntime + Clone,
+    I: IdentityManager,
+{
+    info!("module {} is not running; restarting", module_id);
+
+    let runtime_for_create = runtime.clone();
+    let module_id_for_create = module_id.clone();
+
+    identity
+        .create_module_identity(&module_id)
+        .map_err(|err| Error::from(err.context(ErrorKind::Watchdog)))
+        .and_then(move |_| {
+            runtime_for_create
+                .create(spec)
+                .map_err(|err| Error::from(err.context(ErrorKind::Watchdog)))
+                .or_else(|_| future::ok(()))
+        }).and_then(move |()| {
+            runtime
+                .start(&module_id_for_create)
+                .map_err(|err| Error::from(err.context(ErrorKind::Watchdog)))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{advance, HEALTHY_COOLDOWN, INITIAL_BACKOFF, MAX_BACKOFF, POLL_INTERVAL};
+
+    #[test]
+    fn advance_doubles_backoff_when_stopped() {
+        let now = Instant::now();
+        let (backoff, healthy_since, delay) = advance(Some(false), Duration::from_secs(4), None, now);
+
+        assert_eq!(backoff, Duration::from_secs(8));
+        assert_eq!(healthy_since, None);
+        assert_eq!(delay, Duration::from_secs(8));
+    }
+
+    #[test]
+    fn advance_caps_backoff_at_max() {
+        let now = Instant::now();
+        let (backoff, _, delay) = advance(Some(false), MAX_BACKOFF, None, now);
+
+        assert_eq!(backoff, MAX_BACKOFF);
+        assert_eq!(delay, MAX_BACKOFF);
+    }
+
+    #[test]
+    fn advance_keeps_backoff_while_healthy_cooldown_has_not_elapsed() {
+        let now = Instant::now();
+        let healthy_since = now - (HEALTHY_COOLDOWN - Duration::from_secs(1));
+        let (backoff, next_healthy_since, delay) =
+            advance(Some(true), Duration::from_secs(16), Some(healthy_since), now);
+
+        assert_eq!(backoff, Duration::from_secs(16));
+        assert_eq!(next_healthy_since, Some(healthy_since));
+        assert_eq!(delay, POLL_INTERVAL);
+    }
+
+    #[test]
+    fn advance_resets_backoff_once_healthy_cooldown_elapses() {
+        let now = Instant::now();
+        let healthy_since = now - HEALTHY_COOLDOWN;
+        let (backoff, next_healthy_since, _) =
+            advance(Some(true), Duration::from_secs(16), Some(healthy_since), now);
+
+        assert_eq!(backoff, INITIAL_BACKOFF);
+        assert_eq!(next_healthy_since, Some(healthy_since));
+    }
+
+    #[test]
+    fn advance_starts_the_healthy_clock_on_first_healthy_tick() {
+        let now = Instant::now();
+        let (backoff, healthy_since, delay) = advance(Some(true), Duration::from_secs(16), None, now);
+
+        assert_eq!(backoff, Duration::from_secs(16));
+        assert_eq!(healthy_since, Some(now));
+        assert_eq!(delay, POLL_INTERVAL);
+    }
+
+    #[test]
+    fn advance_leaves_state_untouched_when_status_is_unknown() {
+        let now = Instant::now();
+        let healthy_since = Some(now - Duration::from_secs(30));
+        let (backoff, next_healthy_since, delay) = advance(None, Duration::from_secs(4), healthy_since, now);
+
+        assert_eq!(backoff, Duration::from_secs(4));
+        assert_eq!(next_healthy_since, healthy_since);
+        assert_eq!(delay, POLL_INTERVAL);
+    }
 }
@@ -0,0 +1,107 @@
+// Copyright (c) Microsoft. All rights reserved.
+
+use std::fmt;
+use std::fmt::Display;
+
+use edgelet_http::route::RouterError;
+use edgelet_http::IntoResponse;
+use failure::{Backtrace, Context, Fail};
+use hyper::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::{Body, Response, StatusCode};
+use serde_json::json;
+
+#[derive(Debug)]
+pub struct Error {
+    inner: Context<ErrorKind>,
+}
+
+#[derive(Clone, Debug, Eq, Fail, PartialEq)]
+pub enum ErrorKind {
+    #[fail(display = "Could not route request")]
+    Router,
+
+    #[fail(display = "Could not parse request body")]
+    MalformedRequestBody,
+
+    #[fail(display = "Could not sign request")]
+    Sign,
+
+    #[fail(display = "Could not retrieve trust bundle")]
+    TrustBundle,
+
+    #[fail(display = "Could not issue server certificate")]
+    ServerCertificate,
+
+    #[fail(display = "Module generation ID does not match")]
+    GenerationIdMismatch,
+
+    #[fail(display = "Module not found")]
+    ModuleNotFound,
+}
+
+impl Fail for Error {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.inner.cause()
+    }
+
+    fn backtrace(&self) -> Option<&Backtrace> {
+        self.inner.backtrace()
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.inner, f)
+    }
+}
+
+impl Error {
+    pub fn kind(&self) -> &ErrorKind {
+        self.inner.get_context()
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Error {
+            inner: Context::new(kind),
+        }
+    }
+}
+
+impl From<Context<ErrorKind>> for Error {
+    fn from(inner: Context<ErrorKind>) -> Self {
+        Error { inner }
+    }
+}
+
+impl From<RouterError> for Error {
+    fn from(error: RouterError) -> Self {
+        Error {
+            inner: error.context(ErrorKind::Router),
+        }
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response<Body> {
+        let status_code = match *self.kind() {
+            ErrorKind::Router => StatusCode::NOT_FOUND,
+            ErrorKind::MalformedRequestBody => StatusCode::BAD_REQUEST,
+            ErrorKind::GenerationIdMismatch => StatusCode::FORBIDDEN,
+            ErrorKind::ModuleNotFound => StatusCode::NOT_FOUND,
+            ErrorKind::Sign | ErrorKind::TrustBundle | ErrorKind::ServerCertificate => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+
+        let body = json!({ "message": self.to_string() }).to_string();
+
+        Response::builder()
+            .status(status_code)
+            .header(CONTENT_TYPE, "application/json")
+            .header(CONTENT_LENGTH, body.len().to_string().as_str())
+            .body(body.into())
+            .expect("response builder failure")
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright (c) Microsoft. All rights reserved.
+
+mod models;
+mod server_cert;
+mod sign;
+mod trust_bundle;
+
+use edgelet_core::crypto::{CreateCertificate, GetTrustBundle, KeyStore};
+use edgelet_core::identity::IdentityManager;
+use edgelet_http::route::{Builder, Parameters, RegexRecognizer, Router, RouterService};
+use failure::ResultExt;
+use futures::Future;
+use hyper::service::Service;
+use hyper::{Body, Request, Response};
+
+use error::{Error, ErrorKind};
+use workload::server_cert::ServerCertHandler;
+use workload::sign::SignHandler;
+use workload::trust_bundle::TrustBundleHandler;
+
+pub struct WorkloadService {
+    inner: RouterService<RegexRecognizer>,
+}
+
+impl WorkloadService {
+    // clippy bug: https://github.com/rust-lang-nursery/rust-clippy/issues/3220
+    #[cfg_attr(feature = "cargo-clippy", allow(new_ret_no_self))]
+    pub fn new<K, C, I>(key_store: K, crypto: C, identity: I) -> impl Future<Item = Self, Error = Error>
+    where
+        K: 'static + KeyStore + Clone + Send + Sync,
+        C: 'static + CreateCertificate + GetTrustBundle + Clone + Send + Sync,
+        I: 'static + IdentityManager + Clone + Send + Sync,
+    {
+        let router = router!(
+            post "/modules/(?P<name>[^/]+)/genid/(?P<genid>[^/]+)/sign" => SignHandler::new(key_store, identity.clone()),
+            get "/trust-bundle" => TrustBundleHandler::new(crypto.clone()),
+            post "/modules/(?P<name>[^/]+)/genid/(?P<genid>[^/]+)/certificate/server" => ServerCertHandler::new(crypto, identity),
+        );
+
+        router
+            .new_service()
+            .map_err(Error::from)
+            .map(|inner| WorkloadService { inner })
+    }
+}
+
+/// Pulls the `name` and `genid` path segments every workload route below
+/// `/modules/{name}/genid/{genid}/...` captures.
+pub(crate) fn extract_name_and_genid(params: &Parameters) -> Result<(String, String), Error> {
+    let name = params
+        .name("name")
+        .map(String::from)
+        .ok_or_else(|| Error::from(ErrorKind::MalformedRequestBody))?;
+    let genid = params
+        .name("genid")
+        .map(String::from)
+        .ok_or_else(|| Error::from(ErrorKind::MalformedRequestBody))?;
+
+    Ok((name, genid))
+}
+
+/// Rejects the request unless `genid` matches the module's current
+/// generation ID, so a caller can't sign or request certificates for a
+/// module it no longer owns (e.g. after the module was recreated).
+pub(crate) fn validate_generation_id<I>(identity: &I, module_name: &str, genid: &str) -> Result<(), Error>
+where
+    I: IdentityManager,
+{
+    let current = identity
+        .generation_id(module_name)
+        .context(ErrorKind::ModuleNotFound)?;
+
+    compare_generation_id(&current, genid)
+}
+
+fn compare_generation_id(current: &str, requested: &str) -> Result<(), Error> {
+    if current == requested {
+        Ok(())
+    } else {
+        Err(Error::from(ErrorKind::GenerationIdMismatch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_generation_id_matches() {
+        assert!(compare_generation_id("abc123", "abc123").is_ok());
+    }
+
+    #[test]
+    fn compare_generation_id_mismatches() {
+        let err = compare_generation_id("abc123", "def456").unwrap_err();
+        assert_eq!(&ErrorKind::GenerationIdMismatch, err.kind());
+    }
+}
+
+impl Service for WorkloadService {
+    type ReqBody = Body;
+    type ResBody = Body;
+    type Error = Error;
+    type Future = Box<dyn Future<Item = Response<Self::ResBody>, Error = Self::Error> + Send>;
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        Box::new(self.inner.call(req).map_err(Error::from))
+    }
+}
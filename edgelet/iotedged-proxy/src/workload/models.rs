@@ -0,0 +1,87 @@
+// Copyright (c) Microsoft. All rights reserved.
+
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SignRequest {
+    #[serde(rename = "keyId")]
+    key_id: String,
+
+    algo: String,
+
+    #[serde(rename = "data")]
+    data: String,
+}
+
+impl SignRequest {
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub fn algo(&self) -> &str {
+        &self.algo
+    }
+
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SignResponse {
+    digest: String,
+}
+
+impl SignResponse {
+    pub fn new(digest: String) -> Self {
+        SignResponse { digest }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrustBundleResponse {
+    certificate: String,
+}
+
+impl TrustBundleResponse {
+    pub fn new(certificate: String) -> Self {
+        TrustBundleResponse { certificate }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServerCertificateRequest {
+    #[serde(rename = "commonName")]
+    common_name: String,
+
+    expiration: String,
+}
+
+impl ServerCertificateRequest {
+    pub fn common_name(&self) -> &str {
+        &self.common_name
+    }
+
+    pub fn expiration(&self) -> &str {
+        &self.expiration
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CertificateResponse {
+    #[serde(rename = "privateKey")]
+    private_key: String,
+
+    certificate: String,
+    expiration: String,
+}
+
+impl CertificateResponse {
+    pub fn new(private_key: String, certificate: String, expiration: String) -> Self {
+        CertificateResponse {
+            private_key,
+            certificate,
+            expiration,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+// Copyright (c) Microsoft. All rights reserved.
+
+use edgelet_core::crypto::CreateCertificate;
+use edgelet_core::identity::IdentityManager;
+use edgelet_http::route::{Handler, Parameters};
+use failure::{Fail, ResultExt};
+use futures::{Future, IntoFuture, Stream};
+use hyper::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use serde_json;
+
+use error::{Error, ErrorKind};
+use workload::models::{CertificateResponse, ServerCertificateRequest};
+use workload::{extract_name_and_genid, validate_generation_id};
+
+pub struct ServerCertHandler<C, I> {
+    crypto: C,
+    identity: I,
+}
+
+impl<C, I> ServerCertHandler<C, I> {
+    pub fn new(crypto: C, identity: I) -> Self {
+        ServerCertHandler { crypto, identity }
+    }
+}
+
+impl<C, I> Handler<Parameters> for ServerCertHandler<C, I>
+where
+    C: 'static + CreateCertificate + Clone + Send + Sync,
+    I: 'static + IdentityManager + Clone + Send + Sync,
+{
+    fn handle(
+        &self,
+        req: Request<Body>,
+        params: Parameters,
+    ) -> Box<dyn Future<Item = Response<Body>, Error = Error> + Send> {
+        let crypto = self.crypto.clone();
+        let identity = self.identity.clone();
+
+        let response = extract_name_and_genid(&params)
+            .and_then(move |(module_name, genid)| {
+                validate_generation_id(&identity, &module_name, &genid).map(|()| module_name)
+            }).into_future()
+            .and_then(move |module_name| {
+                req.into_body()
+                    .concat2()
+                    .map_err(|err| Error::from(err.context(ErrorKind::MalformedRequestBody)))
+                    .and_then(move |body| {
+                        let cert_request: ServerCertificateRequest =
+                            serde_json::from_slice(&body).context(ErrorKind::MalformedRequestBody)?;
+
+                        let cert = crypto
+                            .create_certificate(
+                                &module_name,
+                                cert_request.common_name(),
+                                cert_request.expiration(),
+                            ).context(ErrorKind::ServerCertificate)?;
+
+                        let response = CertificateResponse::new(
+                            cert.private_key().to_owned(),
+                            cert.pem().to_owned(),
+                            cert_request.expiration().to_owned(),
+                        );
+                        let body = serde_json::to_string(&response)
+                            .context(ErrorKind::ServerCertificate)?;
+
+                        Ok(Response::builder()
+                            .status(StatusCode::CREATED)
+                            .header(CONTENT_TYPE, "application/json")
+                            .header(CONTENT_LENGTH, body.len().to_string().as_str())
+                            .body(body.into())
+                            .expect("response builder failure"))
+                    })
+            });
+
+        Box::new(response)
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright (c) Microsoft. All rights reserved.
+
+use base64;
+use edgelet_core::crypto::{KeyIdentity, KeyStore, Sign, SignatureAlgorithm};
+use edgelet_core::identity::IdentityManager;
+use edgelet_http::route::{Handler, Parameters};
+use failure::{Fail, ResultExt};
+use futures::{Future, IntoFuture, Stream};
+use hyper::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use serde_json;
+
+use error::{Error, ErrorKind};
+use workload::models::{SignRequest, SignResponse};
+use workload::{extract_name_and_genid, validate_generation_id};
+
+pub struct SignHandler<K, I> {
+    key_store: K,
+    identity: I,
+}
+
+impl<K, I> SignHandler<K, I> {
+    pub fn new(key_store: K, identity: I) -> Self {
+        SignHandler { key_store, identity }
+    }
+}
+
+impl<K, I> Handler<Parameters> for SignHandler<K, I>
+where
+    K: 'static + KeyStore + Clone + Send + Sync,
+    I: 'static + IdentityManager + Clone + Send + Sync,
+{
+    fn handle(
+        &self,
+        req: Request<Body>,
+        params: Parameters,
+    ) -> Box<dyn Future<Item = Response<Body>, Error = Error> + Send> {
+        let key_store = self.key_store.clone();
+        let identity = self.identity.clone();
+
+        let response = extract_name_and_genid(&params)
+            .and_then(move |(module_name, genid)| {
+                validate_generation_id(&identity, &module_name, &genid).map(|()| module_name)
+            }).into_future()
+            .and_then(move |module_name| {
+                req.into_body()
+                    .concat2()
+                    .map_err(|err| Error::from(err.context(ErrorKind::MalformedRequestBody)))
+                    .and_then(move |body| {
+                        let sign_request: SignRequest = serde_json::from_slice(&body)
+                            .context(ErrorKind::MalformedRequestBody)?;
+
+                        let algorithm = parse_signature_algorithm(sign_request.algo())?;
+
+                        let key = key_store
+                            .get(&KeyIdentity::Module(module_name), sign_request.key_id())
+                            .context(ErrorKind::Sign)?;
+
+                        let digest = key
+                            .sign(algorithm, sign_request.data().as_bytes())
+                            .context(ErrorKind::Sign)?;
+
+                        let response = SignResponse::new(base64::encode(digest.as_bytes()));
+                        let body = serde_json::to_string(&response).context(ErrorKind::Sign)?;
+
+                        Ok(Response::builder()
+                            .status(StatusCode::OK)
+                            .header(CONTENT_TYPE, "application/json")
+                            .header(CONTENT_LENGTH, body.len().to_string().as_str())
+                            .body(body.into())
+                            .expect("response builder failure"))
+                    })
+            });
+
+        Box::new(response)
+    }
+}
+
+fn parse_signature_algorithm(algo: &str) -> Result<SignatureAlgorithm, Error> {
+    match algo {
+        "HMACSHA256" => Ok(SignatureAlgorithm::HMACSHA256),
+        _ => Err(Error::from(ErrorKind::MalformedRequestBody)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_signature_algorithm_accepts_hmacsha256() {
+        match parse_signature_algorithm("HMACSHA256") {
+            Ok(SignatureAlgorithm::HMACSHA256) => (),
+            _ => panic!("expected HMACSHA256"),
+        }
+    }
+
+    #[test]
+    fn parse_signature_algorithm_rejects_unsupported_value() {
+        let err = parse_signature_algorithm("RS256").unwrap_err();
+        assert_eq!(&ErrorKind::MalformedRequestBody, err.kind());
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright (c) Microsoft. All rights reserved.
+
+use edgelet_core::crypto::GetTrustBundle;
+use edgelet_http::route::{Handler, Parameters};
+use failure::{Fail, ResultExt};
+use futures::{future, Future};
+use hyper::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use serde_json;
+
+use error::{Error, ErrorKind};
+use workload::models::TrustBundleResponse;
+
+pub struct TrustBundleHandler<C> {
+    crypto: C,
+}
+
+impl<C> TrustBundleHandler<C> {
+    pub fn new(crypto: C) -> Self {
+        TrustBundleHandler { crypto }
+    }
+}
+
+impl<C> Handler<Parameters> for TrustBundleHandler<C>
+where
+    C: 'static + GetTrustBundle + Send + Sync,
+{
+    fn handle(
+        &self,
+        _req: Request<Body>,
+        _params: Parameters,
+    ) -> Box<dyn Future<Item = Response<Body>, Error = Error> + Send> {
+        let response = self
+            .crypto
+            .get_trust_bundle()
+            .map_err(|err| Error::from(err.context(ErrorKind::TrustBundle)))
+            .and_then(|certificate| {
+                let response = TrustBundleResponse::new(certificate.pem().to_owned());
+                let body =
+                    serde_json::to_string(&response).context(ErrorKind::TrustBundle)?;
+
+                Ok(Response::builder()
+                    .status(StatusCode::OK)
+                    .header(CONTENT_TYPE, "application/json")
+                    .header(CONTENT_LENGTH, body.len().to_string().as_str())
+                    .body(body.into())
+                    .expect("response builder failure"))
+            });
+
+        Box::new(future::result(response))
+    }
+}
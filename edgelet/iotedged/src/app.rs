@@ -1,15 +1,22 @@
 // Copyright (c) Microsoft. All rights reserved.
 
+use std::env;
 use std::fs;
+use std::process;
 use std::str::FromStr;
 
-use clap::{App, Arg, ArgMatches};
+use clap::{App, Arg, ArgMatches, SubCommand};
 use edgelet_core;
 use edgelet_docker::{DockerModuleRuntime, Settings as DockerSettings};
 use failure::ResultExt;
 
 use error::{Error, ErrorKind, InitializeErrorReason};
-use logging;
+use logging::{self, Level};
+use serde_json::{self, Value};
+
+mod check;
+
+use self::check::run_checks;
 
 #[cfg(unix)]
 static DEFAULTS: &str = include_str!("config/unix/default.yaml");
@@ -17,6 +24,9 @@ static DEFAULTS: &str = include_str!("config/unix/default.yaml");
 #[cfg(windows)]
 static DEFAULTS: &str = include_str!("config/windows/default.yaml");
 
+const ENV_OVERRIDE_PREFIX: &str = "IOTEDGE_";
+const ENV_OVERRIDE_SEPARATOR: &str = "__";
+
 pub fn create_base_app<'a, 'b>() -> App<'a, 'b> {
     App::new(crate_name!())
         .version(crate_version!())
@@ -29,6 +39,15 @@ pub fn create_base_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("FILE")
                 .help("Sets daemon configuration file")
                 .takes_value(true),
+        ).arg(
+            Arg::with_name("verbose")
+                .short("v")
+                .long("verbose")
+                .multiple(true)
+                .help("Sets the logging verbosity; repeat for more detail (-v = debug, -vv = trace). Overridden by RUST_LOG when it is set."),
+        ).subcommand(
+            SubCommand::with_name("check")
+                .about("Run preflight diagnostics against the configured daemon settings"),
         )
 }
 
@@ -68,38 +87,166 @@ pub fn init_common<'a>() -> Result<(DockerSettings, ArgMatches<'a>), Error> {
                 Ok(DEFAULTS.to_string())
             }).context(ErrorKind::Initialize(InitializeErrorReason::LoadSettings))?;
 
-        DockerSettings::from_str(&config_str)
-            .context(ErrorKind::Initialize(InitializeErrorReason::LoadSettings))?
+        let settings = DockerSettings::from_str(&config_str)
+            .context(ErrorKind::Initialize(InitializeErrorReason::LoadSettings))?;
+
+        apply_env_overrides(settings)?
     };
 
     Ok((settings, matches))
 }
 
+/// Overlays `IOTEDGE_`-prefixed environment variables onto `settings`, e.g.
+/// `IOTEDGE_AGENT__CONFIG__IMAGE` overrides `agent.config.image`.
+fn apply_env_overrides(settings: DockerSettings) -> Result<DockerSettings, Error> {
+    let overrides: Vec<_> = env::vars()
+        .filter_map(|(key, value)| {
+            if key.starts_with(ENV_OVERRIDE_PREFIX) {
+                Some((key[ENV_OVERRIDE_PREFIX.len()..].to_owned(), value))
+            } else {
+                None
+            }
+        }).collect();
+
+    if overrides.is_empty() {
+        return Ok(settings);
+    }
+
+    let mut value = serde_json::to_value(&settings)
+        .context(ErrorKind::Initialize(InitializeErrorReason::LoadSettings))?;
+
+    for (path, override_value) in overrides {
+        let segments: Vec<_> = path.to_lowercase().split(ENV_OVERRIDE_SEPARATOR).map(str::to_owned).collect();
+        info!("Overriding {} from environment", segments.join("."));
+        set_override(&mut value, &segments, &override_value);
+    }
+
+    serde_json::from_value(value)
+        .context(ErrorKind::Initialize(InitializeErrorReason::LoadSettings))
+        .map_err(Error::from)
+}
+
+fn set_override(value: &mut Value, segments: &[String], override_value: &str) {
+    if segments.is_empty() {
+        return;
+    }
+
+    if !value.is_object() {
+        *value = Value::Object(serde_json::Map::new());
+    }
+
+    let map = value.as_object_mut().expect("value was just made an object");
+    let entry = map.entry(segments[0].clone()).or_insert(Value::Null);
+
+    if segments.len() == 1 {
+        *entry = Value::String(override_value.to_owned());
+    } else {
+        set_override(entry, &segments[1..], override_value);
+    }
+}
+
+/// Returns `true` if the daemon was invoked as `iotedged check` rather than
+/// to run the daemon itself.
+pub fn is_check(matches: &ArgMatches) -> bool {
+    matches.subcommand_matches("check").is_some()
+}
+
+/// Runs the preflight diagnostics and returns `true` unless any of them
+/// failed.
+pub fn check(settings: DockerSettings) -> bool {
+    run_checks(settings)
+}
+
+/// Derives the logging level from the `-v`/`--verbose` flags (none = info,
+/// `-v` = debug, `-vv` or more = trace).
+fn level_from_matches(matches: &ArgMatches) -> Level {
+    match matches.occurrences_of("verbose") {
+        0 => Level::Info,
+        1 => Level::Debug,
+        _ => Level::Trace,
+    }
+}
+
 #[cfg(target_os = "windows")]
 pub fn init() -> Result<(DockerModuleRuntime, DockerSettings), Error> {
     let (settings, matches) = init_common()?;
+    let level = level_from_matches(&matches);
 
     if matches.is_present("use-event-logger") {
-        logging::init_win_log();
+        logging::init_win_log(level);
     } else {
-        logging::init();
+        logging::init(level);
+    }
+
+    if is_check(&matches) {
+        process::exit(if check(settings) { 0 } else { 1 });
     }
 
     log_banner();
 
-    Ok(DockerModuleRuntime::new(), settings)
+    Ok((DockerModuleRuntime::new(), settings))
 }
 
 #[cfg(not(target_os = "windows"))]
 pub fn init() -> Result<(DockerModuleRuntime, DockerSettings), Error> {
-    logging::init();
+    let (settings, matches) = init_common()?;
+    logging::init(level_from_matches(&matches));
+
+    if is_check(&matches) {
+        process::exit(if check(settings) { 0 } else { 1 });
+    }
+
     log_banner();
-    init_common().map(|(settings, _)| (DockerModuleRuntime::new(), settings))
+    Ok((DockerModuleRuntime::new(), settings))
 }
 
 #[cfg(target_os = "windows")]
 pub fn init_win_svc() -> Result<(DockerModuleRuntime, DockerSettings), Error> {
-    logging::init_win_log();
+    let (settings, matches) = init_common()?;
+    logging::init_win_log(level_from_matches(&matches));
+
+    if is_check(&matches) {
+        process::exit(if check(settings) { 0 } else { 1 });
+    }
+
     log_banner();
-    init_common().map(|(settings, _)| (DockerModuleRuntime::new(), settings))
+    Ok((DockerModuleRuntime::new(), settings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn set_override_creates_nested_path() {
+        let mut value = Value::Null;
+        set_override(
+            &mut value,
+            &["agent".to_owned(), "config".to_owned(), "image".to_owned()],
+            "foo:1.2",
+        );
+
+        assert_eq!(value, json!({ "agent": { "config": { "image": "foo:1.2" } } }));
+    }
+
+    #[test]
+    fn set_override_overwrites_existing_leaf() {
+        let mut value = json!({ "agent": { "config": { "image": "old:1.0" } } });
+        set_override(
+            &mut value,
+            &["agent".to_owned(), "config".to_owned(), "image".to_owned()],
+            "new:2.0",
+        );
+
+        assert_eq!(value["agent"]["config"]["image"], json!("new:2.0"));
+    }
+
+    #[test]
+    fn set_override_is_noop_for_empty_path() {
+        let mut value = json!({ "agent": "unchanged" });
+        set_override(&mut value, &[], "ignored");
+
+        assert_eq!(value, json!({ "agent": "unchanged" }));
+    }
 }
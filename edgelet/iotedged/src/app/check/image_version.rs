@@ -0,0 +1,116 @@
+// Copyright (c) Microsoft. All rights reserved.
+
+use edgelet_core;
+use regex::Regex;
+
+use app::check::{Check, CheckResult, Checker};
+
+lazy_static! {
+    static ref IMAGE_REF_REGEX: Regex =
+        Regex::new(r"^(.*?):([^/]+)$").expect("image reference regex failed to compile");
+}
+
+/// Verifies that the Edge Agent image configured in `settings` is at least
+/// as new as the version of `iotedged` running the check.
+#[derive(Default)]
+pub struct ImageVersion;
+
+impl Checker for ImageVersion {
+    fn name(&self) -> &str {
+        "Edge Agent image version"
+    }
+
+    fn execute(&mut self, check: &mut Check) -> CheckResult {
+        let image = check.settings().agent().config().image();
+        let (repo, tag) = split_repo_tag(image);
+
+        let configured = match parse_major_minor(&tag) {
+            Some(version) => version,
+            None => {
+                return CheckResult::Failed(format!(
+                    "could not determine a version from the image tag {:?} on {:?}",
+                    tag, repo
+                ));
+            }
+        };
+
+        let expected = parse_major_minor(edgelet_core::version())
+            .expect("edgelet_core::version() did not start with a MAJOR.MINOR version");
+
+        if configured < expected {
+            CheckResult::Warning(format!(
+                "configured Edge Agent image {}:{} is older than the expected version {}.{}",
+                repo, tag, expected.0, expected.1
+            ))
+        } else {
+            CheckResult::Ok
+        }
+    }
+}
+
+fn split_repo_tag(image: &str) -> (String, String) {
+    match IMAGE_REF_REGEX.captures(image) {
+        Some(captures) => (
+            captures.get(1).map_or("", |m| m.as_str()).to_owned(),
+            captures.get(2).map_or("latest", |m| m.as_str()).to_owned(),
+        ),
+        None => (image.to_owned(), "latest".to_owned()),
+    }
+}
+
+fn parse_major_minor(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.splitn(3, '.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_repo_tag_splits_on_trailing_colon() {
+        assert_eq!(
+            split_repo_tag("mcr.microsoft.com/azureiotedge-agent:1.2"),
+            (
+                "mcr.microsoft.com/azureiotedge-agent".to_owned(),
+                "1.2".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn split_repo_tag_defaults_missing_tag_to_latest() {
+        assert_eq!(
+            split_repo_tag("mcr.microsoft.com/azureiotedge-agent"),
+            (
+                "mcr.microsoft.com/azureiotedge-agent".to_owned(),
+                "latest".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn split_repo_tag_does_not_mistake_a_port_for_a_tag() {
+        assert_eq!(
+            split_repo_tag("myregistry.io:5000/azureiotedge-agent"),
+            (
+                "myregistry.io:5000/azureiotedge-agent".to_owned(),
+                "latest".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_major_minor_parses_leading_components() {
+        assert_eq!(parse_major_minor("1.2"), Some((1, 2)));
+        assert_eq!(parse_major_minor("1.2.3"), Some((1, 2)));
+    }
+
+    #[test]
+    fn parse_major_minor_rejects_unparseable_tags() {
+        assert_eq!(parse_major_minor("latest"), None);
+        assert_eq!(parse_major_minor("1"), None);
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright (c) Microsoft. All rights reserved.
+
+use edgelet_docker::Settings as DockerSettings;
+
+mod image_version;
+
+use self::image_version::ImageVersion;
+
+/// The outcome of running a single [`Checker`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum CheckResult {
+    Ok,
+    Warning(String),
+    Skipped,
+    Failed(String),
+    Fatal(String),
+}
+
+/// Context shared with every [`Checker`] while it runs.
+pub struct Check {
+    settings: DockerSettings,
+}
+
+impl Check {
+    pub fn new(settings: DockerSettings) -> Self {
+        Check { settings }
+    }
+
+    pub fn settings(&self) -> &DockerSettings {
+        &self.settings
+    }
+}
+
+/// A single preflight diagnostic run by `iotedged check`.
+pub trait Checker {
+    fn name(&self) -> &str;
+    fn execute(&mut self, check: &mut Check) -> CheckResult;
+}
+
+fn checks() -> Vec<Box<dyn Checker>> {
+    vec![Box::new(ImageVersion::default())]
+}
+
+/// Runs every registered check against `settings`, logging the outcome of
+/// each one. Returns `false` if any check produced `Failed` or `Fatal`, and
+/// `true` otherwise (warnings and skipped checks do not fail the run).
+pub fn run_checks(settings: DockerSettings) -> bool {
+    let mut check = Check::new(settings);
+    let mut ok = true;
+
+    for mut checker in checks() {
+        match checker.execute(&mut check) {
+            CheckResult::Ok => info!("\u{2714} {} - OK", checker.name()),
+            CheckResult::Warning(message) => {
+                warn!("\u{203c} {} - Warning: {}", checker.name(), message)
+            }
+            CheckResult::Skipped => info!("\u{2014} {} - Skipped", checker.name()),
+            CheckResult::Failed(message) => {
+                error!("\u{2716} {} - Failed: {}", checker.name(), message);
+                ok = false;
+            }
+            CheckResult::Fatal(message) => {
+                error!("\u{2716} {} - Fatal: {}", checker.name(), message);
+                ok = false;
+            }
+        }
+    }
+
+    ok
+}
@@ -0,0 +1,50 @@
+// Copyright (c) Microsoft. All rights reserved.
+
+use std::env;
+use std::fmt;
+
+use env_logger::Builder;
+#[cfg(windows)]
+use win_logger;
+
+const RUST_LOG_ENV: &str = "RUST_LOG";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Level {
+    Info,
+    Debug,
+    Trace,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+pub fn init(level: Level) {
+    Builder::new().parse_filters(&resolve_filter(level)).init();
+}
+
+#[cfg(windows)]
+pub fn init_win_log(level: Level) {
+    win_logger::init("iotedged", &resolve_filter(level)).expect("could not initialize event logger");
+}
+
+fn resolve_filter(level: Level) -> String {
+    match env::var(RUST_LOG_ENV) {
+        Ok(filter) => {
+            eprintln!(
+                "warning: RUST_LOG={} overrides the verbosity requested with -v ({})",
+                filter, level
+            );
+            filter
+        }
+        Err(_) => level.to_string(),
+    }
+}